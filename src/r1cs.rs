@@ -1,16 +1,58 @@
 use std::fs::File;
 use std::path::Path;
-use std::io::{self, Read, Seek, SeekFrom};
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
 use std::fmt;
-use byteorder::{LittleEndian, ReadBytesExt};
-use ark_bls12_381::Fr;
-use ark_ff::{PrimeField, Zero};
-use ark_serialize::SerializationError;
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use ark_ff::{BigInteger, PrimeField};
+use thiserror::Error;
+
+/// Errors that can occur while parsing an R1CS file or a `.wtns` witness file.
+/// Section size and field mismatches carry the byte offset at which they were
+/// detected; the other variants, including I/O errors such as a truncated file,
+/// carry no position.
+#[derive(Debug, Error)]
+pub enum R1CSError {
+    #[error("invalid file: unexpected magic bytes {0:?}")]
+    BadMagic([u8; 4]),
+
+    #[error("unsupported file format version: {0}")]
+    UnsupportedVersion(u32),
+
+    #[error("file is missing its header section")]
+    MissingHeaderSection,
+
+    #[error("R1CS file is missing its constraints section")]
+    MissingConstraintsSection,
+
+    #[error("witness file is missing its values section")]
+    MissingWitnessValuesSection,
+
+    #[error(
+        "section {section_type} at offset {offset} has size mismatch: expected {expected} bytes, found {actual}"
+    )]
+    SectionSizeMismatch {
+        section_type: u32,
+        offset: u64,
+        expected: u64,
+        actual: u64,
+    },
+
+    #[error("file's prime field modulus does not match the target field at offset {offset}")]
+    FieldMismatch { offset: u64 },
+
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
 
 /// Wrapper for R1CS file data with additional utility methods
-pub struct R1CS {
+pub struct R1CS<F: PrimeField> {
     header: R1CSHeader,
-    constraints: Vec<R1CSConstraint>,
+    constraints: Vec<R1CSConstraint<F>>,
+    wire_mapping: Vec<u64>,
+    custom_gates: Vec<CustomGate<F>>,
+    custom_gate_uses: Vec<CustomGateApplication>,
 }
 
 /// Structure to hold R1CS header information
@@ -22,31 +64,168 @@ pub struct R1CSHeader {
     pub n_pub_out: u32,
     pub n_pub_in: u32,
     pub n_prvt_in: u32,
+    pub n_labels: u64,
     pub n_constraints: u32,
 }
 
 /// Represents a term in a linear combination (wire index and coefficient)
 #[derive(Debug, Clone)]
-pub struct Term {
+pub struct Term<F: PrimeField> {
     pub wire_id: u32,
-    pub coefficient: Fr,
+    pub coefficient: F,
 }
 
 /// Represents an R1CS constraint in a more accessible format
 #[derive(Debug, Clone)]
-pub struct R1CSConstraint {
-    pub a_terms: Vec<Term>,
-    pub b_terms: Vec<Term>,
-    pub c_terms: Vec<Term>,
+pub struct R1CSConstraint<F: PrimeField> {
+    pub a_terms: Vec<Term<F>>,
+    pub b_terms: Vec<Term<F>>,
+    pub c_terms: Vec<Term<F>>,
+}
+
+/// A custom gate definition from the custom-gates-list section (type 4): a name
+/// plus the field-element parameters it was instantiated with.
+#[derive(Debug, Clone)]
+pub struct CustomGate<F: PrimeField> {
+    pub name: String,
+    pub parameters: Vec<F>,
+}
+
+/// A use of a custom gate from the custom-gates-application section (type 5):
+/// which gate was applied and the wire signals it acts on.
+#[derive(Debug, Clone)]
+pub struct CustomGateApplication {
+    pub gate_id: u32,
+    pub signals: Vec<u32>,
+}
+
+/// A streaming iterator over the constraints of an R1CS file, decoding one
+/// constraint per call to `next()` instead of holding them all in memory at once.
+pub struct ConstraintsIter<F: PrimeField> {
+    reader: BufReader<File>,
+    field_size: u32,
+    remaining: u32,
+    _field: PhantomData<F>,
+}
+
+impl<F: PrimeField> Iterator for ConstraintsIter<F> {
+    type Item = Result<R1CSConstraint<F>, R1CSError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let constraint = (|| -> Result<R1CSConstraint<F>, R1CSError> {
+            let a_terms = R1CS::<F>::read_linear_combination(&mut self.reader, self.field_size)?;
+            let b_terms = R1CS::<F>::read_linear_combination(&mut self.reader, self.field_size)?;
+            let c_terms = R1CS::<F>::read_linear_combination(&mut self.reader, self.field_size)?;
+            Ok(R1CSConstraint { a_terms, b_terms, c_terms })
+        })();
+
+        self.remaining -= 1;
+        Some(constraint)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.remaining as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<F: PrimeField> FusedIterator for ConstraintsIter<F> {}
+
+/// Position and size of a section within an R1CS file, as collected by the first pass over the section table.
+struct SectionInfo {
+    section_type: u32,
+    position: u64,
+    size: u64,
+}
+
+/// Check whether a little-endian prime modulus read from a file matches `F::MODULUS`,
+/// ignoring any zero padding past the shorter of the two byte widths.
+fn field_modulus_matches<F: PrimeField>(prime_bytes: &[u8]) -> bool {
+    let expected = F::MODULUS.to_bytes_le();
+    let mut actual = prime_bytes.to_vec();
+
+    while actual.len() > expected.len() && actual.last() == Some(&0) {
+        actual.pop();
+    }
+    let mut expected_trimmed = expected.clone();
+    while expected_trimmed.len() > actual.len() && expected_trimmed.last() == Some(&0) {
+        expected_trimmed.pop();
+    }
+
+    actual == expected_trimmed
+}
+
+/// A circom witness (`.wtns`), holding one field element per wire in assignment order.
+pub struct Witness<F: PrimeField> {
+    pub values: Vec<F>,
 }
 
-impl fmt::Display for Term {
+impl<F: PrimeField> Witness<F> {
+    /// Load a witness from circom's binary `.wtns` format: magic `wtns`, a version, a
+    /// section table, a field-element header section, and a values section holding
+    /// `n_witness` little-endian field elements.
+    pub fn read_wtns<P: AsRef<Path>>(path: P) -> Result<Self, R1CSError> {
+        let mut file = File::open(&path)?;
+
+        let mut magic = [0u8; 4];
+        file.read_exact(&mut magic)?;
+        if &magic != b"wtns" {
+            return Err(R1CSError::BadMagic(magic));
+        }
+
+        let version = file.read_u32::<LittleEndian>()?;
+        if version != 2 {
+            return Err(R1CSError::UnsupportedVersion(version));
+        }
+
+        let num_sections = file.read_u32::<LittleEndian>()?;
+        let mut sections = Vec::with_capacity(num_sections as usize);
+        for _ in 0..num_sections {
+            let section_type = file.read_u32::<LittleEndian>()?;
+            let section_size = file.read_u64::<LittleEndian>()?;
+            let section_pos = file.stream_position()?;
+            sections.push(SectionInfo { section_type, position: section_pos, size: section_size });
+            file.seek(SeekFrom::Start(section_pos + section_size))?;
+        }
+
+        let header_section = sections.iter().find(|s| s.section_type == 1)
+            .ok_or(R1CSError::MissingHeaderSection)?;
+        file.seek(SeekFrom::Start(header_section.position))?;
+
+        let field_size = file.read_u32::<LittleEndian>()?;
+        let mut prime_bytes = vec![0u8; field_size as usize];
+        file.read_exact(&mut prime_bytes)?;
+        if !field_modulus_matches::<F>(&prime_bytes) {
+            return Err(R1CSError::FieldMismatch { offset: file.stream_position()? });
+        }
+        let n_witness = file.read_u32::<LittleEndian>()?;
+
+        let values_section = sections.iter().find(|s| s.section_type == 2)
+            .ok_or(R1CSError::MissingWitnessValuesSection)?;
+        file.seek(SeekFrom::Start(values_section.position))?;
+
+        let mut values = Vec::with_capacity(n_witness as usize);
+        for _ in 0..n_witness {
+            let mut bytes = vec![0u8; field_size as usize];
+            file.read_exact(&mut bytes)?;
+            values.push(F::from_le_bytes_mod_order(&bytes));
+        }
+
+        Ok(Self { values })
+    }
+}
+
+impl<F: PrimeField> fmt::Display for Term<F> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}·x{}", self.coefficient, self.wire_id)
     }
 }
 
-impl fmt::Display for R1CSConstraint {
+impl<F: PrimeField> fmt::Display for R1CSConstraint<F> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // Format A terms
         let a_str = if self.a_terms.is_empty() {
@@ -82,85 +261,79 @@ impl fmt::Display for R1CSConstraint {
     }
 }
 
-impl R1CS {
-    /// Read and parse an R1CS file using direct I/O operations
-    pub fn read<P: AsRef<Path>>(path: P) -> io::Result<Self> {
-        println!("Reading R1CS file from: {}", path.as_ref().display());
-        
-        let mut file = File::open(&path)?;
-        
+impl<F: PrimeField> R1CS<F> {
+    /// Read the magic/version preamble and the section table of an R1CS file,
+    /// leaving `file` positioned right after the section table.
+    fn scan_sections(file: &mut File) -> Result<Vec<SectionInfo>, R1CSError> {
         // Read magic bytes "r1cs"
         let mut magic = [0u8; 4];
         file.read_exact(&mut magic)?;
-        
+
         if &magic != b"r1cs" {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "Invalid R1CS file: wrong magic bytes"
-            ));
+            return Err(R1CSError::BadMagic(magic));
         }
-        
+
         // Read version
         let version = file.read_u32::<LittleEndian>()?;
         if version != 1 {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                format!("Unsupported R1CS version: {}", version)
-            ));
+            return Err(R1CSError::UnsupportedVersion(version));
         }
-        
+
         // Read number of sections
         let num_sections = file.read_u32::<LittleEndian>()?;
         println!("R1CS file has {} sections", num_sections);
-        
-        // Store section positions to handle out-of-order sections
-        struct SectionInfo {
-            section_type: u32,
-            position: u64,
-            size: u64,
-        }
-        
+
         let mut sections = Vec::with_capacity(num_sections as usize);
-        
+
         // First pass: collect information about all sections
         for _ in 0..num_sections {
             let section_type = file.read_u32::<LittleEndian>()?;
             let section_size = file.read_u64::<LittleEndian>()?;
-            let section_pos = file.seek(SeekFrom::Current(0))?;
-            
+            let section_pos = file.stream_position()?;
+
             sections.push(SectionInfo {
                 section_type,
                 position: section_pos,
                 size: section_size,
             });
-            
+
             // Skip to next section
             file.seek(SeekFrom::Start(section_pos + section_size))?;
         }
-        
+
+        Ok(sections)
+    }
+
+    /// Read and parse an R1CS file using direct I/O operations
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, R1CSError> {
+        println!("Reading R1CS file from: {}", path.as_ref().display());
+
+        let mut file = File::open(&path)?;
+        let sections = Self::scan_sections(&mut file)?;
+
         // Look for the header section first
         let mut header = None;
-        
+
         for section in &sections {
             if section.section_type == 1 {
                 file.seek(SeekFrom::Start(section.position))?;
                 println!("Reading header section of size {} bytes", section.size);
-                header = Some(Self::read_header_section(&mut file)?);
+                header = Some(Self::read_header_section(&mut file, section.position, section.size)?);
                 break;
             }
         }
-        
+
         let header = match header {
             Some(h) => h,
-            None => return Err(io::Error::new(
-                io::ErrorKind::InvalidData,
-                "R1CS file is missing header section"
-            )),
+            None => return Err(R1CSError::MissingHeaderSection),
         };
-        
+
+        // Make sure the file's field matches the field we're instantiating with
+        Self::check_field_matches(&header, &mut file)?;
+
         // Now look for constraints section
         let mut constraints = Vec::new();
-        
+
         for section in &sections {
             if section.section_type == 2 {
                 file.seek(SeekFrom::Start(section.position))?;
@@ -169,52 +342,260 @@ impl R1CS {
                 break;
             }
         }
-        
+
         if constraints.is_empty() && header.n_constraints > 0 {
-            println!("Warning: Failed to read any constraints despite header indicating {} constraints", 
+            println!("Warning: Failed to read any constraints despite header indicating {} constraints",
                      header.n_constraints);
         }
-        
+
+        // Now look for the wire-to-label-id map section
+        let mut wire_mapping = Vec::new();
+
+        for section in &sections {
+            if section.section_type == 3 {
+                file.seek(SeekFrom::Start(section.position))?;
+                println!("Reading wire-to-label map section of size {} bytes", section.size);
+                wire_mapping = Self::read_wire_mapping_section(&mut file, &header)?;
+                break;
+            }
+        }
+
+        // Custom-gates-list and custom-gates-application sections, from circom's
+        // custom-gates binary format
+        let mut custom_gates = Vec::new();
+
+        for section in &sections {
+            if section.section_type == 4 {
+                file.seek(SeekFrom::Start(section.position))?;
+                println!("Reading custom gates list section of size {} bytes", section.size);
+                custom_gates = Self::read_custom_gates_section(&mut file, &header)?;
+                break;
+            }
+        }
+
+        let mut custom_gate_uses = Vec::new();
+
+        for section in &sections {
+            if section.section_type == 5 {
+                file.seek(SeekFrom::Start(section.position))?;
+                println!("Reading custom gates application section of size {} bytes", section.size);
+                custom_gate_uses = Self::read_custom_gate_uses_section(&mut file)?;
+                break;
+            }
+        }
+
+        // Any other section types are part of formats this reader doesn't understand yet;
+        // the offset table already lets us skip past them without losing our place.
+        for section in &sections {
+            if ![1, 2, 3, 4, 5].contains(&section.section_type) {
+                println!("Skipping unknown section type {} ({} bytes)", section.section_type, section.size);
+            }
+        }
+
         println!("Successfully parsed R1CS file with {} constraints", constraints.len());
-        
+
         Ok(Self {
             header,
             constraints,
+            wire_mapping,
+            custom_gates,
+            custom_gate_uses,
         })
     }
-    
-    fn read_header_section(file: &mut File) -> io::Result<R1CSHeader> {
+
+    /// Serialize this R1CS back to the binary `.r1cs` format: magic, version 1, a section
+    /// count, then the header and constraints sections (plus a labels section, a custom-gates
+    /// list section and a custom-gates application section, each only when it is non-empty)
+    /// with the same layout `read` expects.
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), R1CSError> {
+        let mut file = File::create(path)?;
+
+        file.write_all(b"r1cs")?;
+        file.write_u32::<LittleEndian>(1)?;
+
+        let header_bytes = self.encode_header_section();
+        let constraints_bytes = self.encode_constraints_section();
+        let wire_mapping_bytes = if self.wire_mapping.is_empty() {
+            None
+        } else {
+            Some(self.encode_wire_mapping_section())
+        };
+        let custom_gates_bytes = if self.custom_gates.is_empty() {
+            None
+        } else {
+            Some(self.encode_custom_gates_section())
+        };
+        let custom_gate_uses_bytes = if self.custom_gate_uses.is_empty() {
+            None
+        } else {
+            Some(self.encode_custom_gate_uses_section())
+        };
+
+        let num_sections = 2
+            + wire_mapping_bytes.is_some() as u32
+            + custom_gates_bytes.is_some() as u32
+            + custom_gate_uses_bytes.is_some() as u32;
+        file.write_u32::<LittleEndian>(num_sections)?;
+
+        file.write_u32::<LittleEndian>(1)?;
+        file.write_u64::<LittleEndian>(header_bytes.len() as u64)?;
+        file.write_all(&header_bytes)?;
+
+        file.write_u32::<LittleEndian>(2)?;
+        file.write_u64::<LittleEndian>(constraints_bytes.len() as u64)?;
+        file.write_all(&constraints_bytes)?;
+
+        if let Some(wire_mapping_bytes) = wire_mapping_bytes {
+            file.write_u32::<LittleEndian>(3)?;
+            file.write_u64::<LittleEndian>(wire_mapping_bytes.len() as u64)?;
+            file.write_all(&wire_mapping_bytes)?;
+        }
+
+        if let Some(custom_gates_bytes) = custom_gates_bytes {
+            file.write_u32::<LittleEndian>(4)?;
+            file.write_u64::<LittleEndian>(custom_gates_bytes.len() as u64)?;
+            file.write_all(&custom_gates_bytes)?;
+        }
+
+        if let Some(custom_gate_uses_bytes) = custom_gate_uses_bytes {
+            file.write_u32::<LittleEndian>(5)?;
+            file.write_u64::<LittleEndian>(custom_gate_uses_bytes.len() as u64)?;
+            file.write_all(&custom_gate_uses_bytes)?;
+        }
+
+        Ok(())
+    }
+
+    fn encode_header_section(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.write_u32::<LittleEndian>(self.header.field_size).unwrap();
+        buf.extend_from_slice(&self.header.prime_bytes);
+        buf.write_u32::<LittleEndian>(self.header.n_wires).unwrap();
+        buf.write_u32::<LittleEndian>(self.header.n_pub_out).unwrap();
+        buf.write_u32::<LittleEndian>(self.header.n_pub_in).unwrap();
+        buf.write_u32::<LittleEndian>(self.header.n_prvt_in).unwrap();
+        buf.write_u64::<LittleEndian>(self.header.n_labels).unwrap();
+        buf.write_u32::<LittleEndian>(self.header.n_constraints).unwrap();
+        buf
+    }
+
+    fn encode_constraints_section(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        for constraint in &self.constraints {
+            Self::encode_linear_combination(&mut buf, &constraint.a_terms, self.header.field_size);
+            Self::encode_linear_combination(&mut buf, &constraint.b_terms, self.header.field_size);
+            Self::encode_linear_combination(&mut buf, &constraint.c_terms, self.header.field_size);
+        }
+        buf
+    }
+
+    fn encode_linear_combination(buf: &mut Vec<u8>, terms: &[Term<F>], field_size: u32) {
+        buf.write_u32::<LittleEndian>(terms.len() as u32).unwrap();
+        for term in terms {
+            buf.write_u32::<LittleEndian>(term.wire_id).unwrap();
+            buf.extend_from_slice(&Self::encode_field_element(&term.coefficient, field_size));
+        }
+    }
+
+    fn encode_wire_mapping_section(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        for &label in &self.wire_mapping {
+            buf.write_u64::<LittleEndian>(label).unwrap();
+        }
+        buf
+    }
+
+    fn encode_custom_gates_section(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.write_u32::<LittleEndian>(self.custom_gates.len() as u32).unwrap();
+        for gate in &self.custom_gates {
+            buf.write_u32::<LittleEndian>(gate.name.len() as u32).unwrap();
+            buf.extend_from_slice(gate.name.as_bytes());
+
+            buf.write_u32::<LittleEndian>(gate.parameters.len() as u32).unwrap();
+            for parameter in &gate.parameters {
+                buf.extend_from_slice(&Self::encode_field_element(parameter, self.header.field_size));
+            }
+        }
+        buf
+    }
+
+    fn encode_custom_gate_uses_section(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.write_u32::<LittleEndian>(self.custom_gate_uses.len() as u32).unwrap();
+        for use_ in &self.custom_gate_uses {
+            buf.write_u32::<LittleEndian>(use_.gate_id).unwrap();
+            buf.write_u32::<LittleEndian>(use_.signals.len() as u32).unwrap();
+            for &signal in &use_.signals {
+                buf.write_u32::<LittleEndian>(signal).unwrap();
+            }
+        }
+        buf
+    }
+
+    // Serialize a field element as a fixed `field_size`-byte little-endian buffer,
+    // zero-padded to match the width `read` expects.
+    fn encode_field_element(value: &F, field_size: u32) -> Vec<u8> {
+        let mut bytes = value.into_bigint().to_bytes_le();
+        bytes.resize(field_size as usize, 0);
+        bytes
+    }
+
+    /// Check that the file's prime field modulus matches `F::MODULUS`, so that
+    /// callers can't silently misinterpret coefficients compiled for a different curve.
+    fn check_field_matches(header: &R1CSHeader, file: &mut File) -> Result<(), R1CSError> {
+        if !field_modulus_matches::<F>(&header.prime_bytes) {
+            return Err(R1CSError::FieldMismatch { offset: file.stream_position()? });
+        }
+
+        Ok(())
+    }
+
+    fn read_header_section(file: &mut File, section_offset: u64, section_size: u64) -> Result<R1CSHeader, R1CSError> {
         // Read field element size (in bytes)
         let field_size = file.read_u32::<LittleEndian>()?;
         println!("  Field size: {} bytes", field_size);
-        
+
+        // The header section layout is a fixed 32 bytes of scalar fields plus the
+        // prime_bytes payload, matching the r1cs-file format used by zkutil.
+        let expected_size = 32 + field_size as u64;
+        if section_size != expected_size {
+            return Err(R1CSError::SectionSizeMismatch {
+                section_type: 1,
+                offset: section_offset,
+                expected: expected_size,
+                actual: section_size,
+            });
+        }
+
         // Read prime field modulus
         let mut prime_bytes = vec![0u8; field_size as usize];
         file.read_exact(&mut prime_bytes)?;
-        
+
         // Read number of wires
         let n_wires = file.read_u32::<LittleEndian>()?;
         println!("  Number of wires: {}", n_wires);
-        
+
         // Read number of public outputs
         let n_pub_out = file.read_u32::<LittleEndian>()?;
         println!("  Number of public outputs: {}", n_pub_out);
-        
+
         // Read number of public inputs
         let n_pub_in = file.read_u32::<LittleEndian>()?;
         println!("  Number of public inputs: {}", n_pub_in);
-        
+
         // Read number of private inputs
         let n_prvt_in = file.read_u32::<LittleEndian>()?;
         println!("  Number of private inputs: {}", n_prvt_in);
-        
+
         // Read number of labels
-        let _n_labels = file.read_u64::<LittleEndian>()?; // Read but ignore if not used
-        
+        let n_labels = file.read_u64::<LittleEndian>()?;
+        println!("  Number of labels: {}", n_labels);
+
         // Read number of constraints
         let n_constraints = file.read_u32::<LittleEndian>()?;
         println!("  Number of constraints: {}", n_constraints);
-        
+
         Ok(R1CSHeader {
             field_size,
             prime_bytes,
@@ -222,82 +603,133 @@ impl R1CS {
             n_pub_out,
             n_pub_in,
             n_prvt_in,
+            n_labels,
             n_constraints,
         })
     }
-    
-    fn read_constraint_section(file: &mut File, header: &R1CSHeader) -> io::Result<Vec<R1CSConstraint>> {
+
+    fn read_wire_mapping_section(file: &mut File, header: &R1CSHeader) -> Result<Vec<u64>, R1CSError> {
+        // The type-3 section holds one label per wire, not `n_labels` entries
+        // (`n_labels` counts signals in the unoptimized circuit, a different quantity).
+        let mut wire_mapping = Vec::with_capacity(header.n_wires as usize);
+
+        for _ in 0..header.n_wires {
+            wire_mapping.push(file.read_u64::<LittleEndian>()?);
+        }
+
+        Ok(wire_mapping)
+    }
+
+    fn read_custom_gates_section(file: &mut File, header: &R1CSHeader) -> Result<Vec<CustomGate<F>>, R1CSError> {
+        let n_custom_gates = file.read_u32::<LittleEndian>()?;
+        let mut custom_gates = Vec::with_capacity(n_custom_gates as usize);
+
+        for _ in 0..n_custom_gates {
+            let name_len = file.read_u32::<LittleEndian>()?;
+            let mut name_bytes = vec![0u8; name_len as usize];
+            file.read_exact(&mut name_bytes)?;
+            let name = String::from_utf8_lossy(&name_bytes).into_owned();
+
+            let n_parameters = file.read_u32::<LittleEndian>()?;
+            let mut parameters = Vec::with_capacity(n_parameters as usize);
+            for _ in 0..n_parameters {
+                let mut param_bytes = vec![0u8; header.field_size as usize];
+                file.read_exact(&mut param_bytes)?;
+                parameters.push(F::from_le_bytes_mod_order(&param_bytes));
+            }
+
+            custom_gates.push(CustomGate { name, parameters });
+        }
+
+        Ok(custom_gates)
+    }
+
+    fn read_custom_gate_uses_section(file: &mut File) -> Result<Vec<CustomGateApplication>, R1CSError> {
+        let n_uses = file.read_u32::<LittleEndian>()?;
+        let mut custom_gate_uses = Vec::with_capacity(n_uses as usize);
+
+        for _ in 0..n_uses {
+            let gate_id = file.read_u32::<LittleEndian>()?;
+            let n_signals = file.read_u32::<LittleEndian>()?;
+            let mut signals = Vec::with_capacity(n_signals as usize);
+            for _ in 0..n_signals {
+                signals.push(file.read_u32::<LittleEndian>()?);
+            }
+
+            custom_gate_uses.push(CustomGateApplication { gate_id, signals });
+        }
+
+        Ok(custom_gate_uses)
+    }
+
+    fn read_constraint_section(file: &mut File, header: &R1CSHeader) -> Result<Vec<R1CSConstraint<F>>, R1CSError> {
         let mut constraints = Vec::with_capacity(header.n_constraints as usize);
-        
+
         for i in 0..header.n_constraints {
             // Read A terms
             let a_terms = Self::read_linear_combination(file, header.field_size)?;
-            
+
             // Read B terms
             let b_terms = Self::read_linear_combination(file, header.field_size)?;
-            
+
             // Read C terms
             let c_terms = Self::read_linear_combination(file, header.field_size)?;
-            
+
             // Capture the lengths before moving the constraint
             let a_len = a_terms.len();
             let b_len = b_terms.len();
             let c_len = c_terms.len();
-            
+
             let constraint = R1CSConstraint { a_terms, b_terms, c_terms };
             constraints.push(constraint);
-            
+
             if i < 3 || i == header.n_constraints - 1 {
-                println!("  Read constraint #{}: {} A terms, {} B terms, {} C terms", 
+                println!("  Read constraint #{}: {} A terms, {} B terms, {} C terms",
                          i, a_len, b_len, c_len);
             } else if i == 3 {
                 println!("  ... and {} more constraints", header.n_constraints - 4);
             }
         }
-        
+
         Ok(constraints)
     }
-    
-    fn read_linear_combination(file: &mut File, field_size: u32) -> io::Result<Vec<Term>> {
+
+    fn read_linear_combination<R: Read + Seek>(file: &mut R, field_size: u32) -> Result<Vec<Term<F>>, R1CSError> {
         // Read number of terms in this linear combination
         let term_count = file.read_u32::<LittleEndian>()?;
         let mut terms = Vec::with_capacity(term_count as usize);
-        
+
         for _ in 0..term_count {
             // Read wire ID
             let wire_id = file.read_u32::<LittleEndian>()?;
-            
+
             // Read coefficient as a field element
             let mut coef_bytes = vec![0u8; field_size as usize];
             file.read_exact(&mut coef_bytes)?;
-            
-            // Convert to Fr element
+
+            // Convert to F element
             // Note: The bytes in R1CS files are in little-endian order
-            let coefficient = match Self::deserialize_fr(&coef_bytes) {
-                Ok(fr) => fr,
-                Err(e) => {
-                    return Err(io::Error::new(
-                        io::ErrorKind::InvalidData,
-                        format!("Failed to deserialize field element: {:?}", e)
-                    ));
-                }
-            };
-            
+            let coefficient = Self::deserialize_field_element(&coef_bytes);
+
             terms.push(Term { wire_id, coefficient });
         }
-        
+
         Ok(terms)
     }
-    
-    // Helper to deserialize Fr elements from R1CS format
-    fn deserialize_fr(bytes: &[u8]) -> Result<Fr, SerializationError> {
+
+    // Helper to deserialize field elements from R1CS format.
+    //
+    // `from_le_bytes_mod_order` reduces its input modulo the field order and never fails,
+    // so a coefficient with the right byte width but a bogus value is silently accepted as
+    // whatever field element it reduces to, rather than rejected as corrupt.
+    fn deserialize_field_element(bytes: &[u8]) -> F {
         // The R1CS file uses little-endian encoding with possible leading/trailing zeros
-        // We need to handle this carefully when deserializing to Fr
-        
+        // We need to handle this carefully when deserializing to F
+
         // Create a smaller buffer with meaningful bytes only
         let mut meaningful_bytes = Vec::new();
         let mut started = false;
-        
+
         // Process in reverse (from most significant to least)
         for &byte in bytes.iter().rev() {
             if byte != 0 || started {
@@ -305,54 +737,131 @@ impl R1CS {
                 meaningful_bytes.push(byte);
             }
         }
-        
+
         // If all bytes were zero
         if meaningful_bytes.is_empty() {
-            return Ok(Fr::zero());
+            return F::zero();
         }
-        
-        // Reverse back to little-endian for Fr deserialization
+
+        // Reverse back to little-endian for F deserialization
         meaningful_bytes.reverse();
-        
-        // Using from_bytes_le for Fr elements
-        Ok(Fr::from_le_bytes_mod_order(&meaningful_bytes))
+
+        // Using from_le_bytes_mod_order for field elements
+        F::from_le_bytes_mod_order(&meaningful_bytes)
     }
-    
+
     /// Get the number of wires in the circuit
     pub fn num_wires(&self) -> u32 {
         self.header.n_wires
     }
-    
+
     /// Get the number of public outputs in the circuit
     pub fn num_public_outputs(&self) -> u32 {
         self.header.n_pub_out
     }
-    
+
     /// Get the number of public inputs in the circuit
     pub fn num_public_inputs(&self) -> u32 {
         self.header.n_pub_in
     }
-    
+
     /// Get the number of private inputs in the circuit
     pub fn num_private_inputs(&self) -> u32 {
         self.header.n_prvt_in
     }
-    
+
     /// Get the number of constraints in the circuit
     pub fn num_constraints(&self) -> u32 {
         self.header.n_constraints
     }
-    
+
     /// Get the prime field modulus from the R1CS file
     pub fn prime_field_modulus(&self) -> &[u8] {
         &self.header.prime_bytes
     }
-    
+
     /// Get all constraints in the circuit, converted to our internal format
-    pub fn constraints(&self) -> &Vec<R1CSConstraint> {
+    pub fn constraints(&self) -> &Vec<R1CSConstraint<F>> {
         &self.constraints
     }
-    
+
+    /// Stream the constraints of an R1CS file one at a time instead of loading them all
+    /// into memory, for circuits with too many constraints to materialize as a `Vec`.
+    pub fn constraints_iter<P: AsRef<Path>>(path: P) -> Result<ConstraintsIter<F>, R1CSError> {
+        let mut file = File::open(&path)?;
+        let sections = Self::scan_sections(&mut file)?;
+
+        let header_section = sections.iter().find(|s| s.section_type == 1)
+            .ok_or(R1CSError::MissingHeaderSection)?;
+        file.seek(SeekFrom::Start(header_section.position))?;
+        let header = Self::read_header_section(&mut file, header_section.position, header_section.size)?;
+
+        // Make sure the file's field matches the field we're instantiating with, same as `read`.
+        Self::check_field_matches(&header, &mut file)?;
+
+        let constraints_section = sections.iter().find(|s| s.section_type == 2)
+            .ok_or(R1CSError::MissingConstraintsSection)?;
+        file.seek(SeekFrom::Start(constraints_section.position))?;
+
+        Ok(ConstraintsIter {
+            reader: BufReader::new(file),
+            field_size: header.field_size,
+            remaining: header.n_constraints,
+            _field: PhantomData,
+        })
+    }
+
+    /// Evaluate every constraint's `a`, `b`, and `c` linear combinations against `witness`
+    /// and check that `a * b == c`, returning the indices of any violated constraints.
+    pub fn check_witness(&self, witness: &[F]) -> Result<(), Vec<usize>> {
+        let violated: Vec<usize> = self.constraints.iter().enumerate()
+            .filter(|(_, constraint)| {
+                let a = Self::eval_linear_combination(&constraint.a_terms, witness);
+                let b = Self::eval_linear_combination(&constraint.b_terms, witness);
+                let c = Self::eval_linear_combination(&constraint.c_terms, witness);
+                // A constraint that references a wire outside the witness can never be
+                // evaluated, let alone satisfied, so treat it as violated rather than panic.
+                match (a, b, c) {
+                    (Some(a), Some(b), Some(c)) => a * b != c,
+                    _ => true,
+                }
+            })
+            .map(|(i, _)| i)
+            .collect();
+
+        if violated.is_empty() {
+            Ok(())
+        } else {
+            Err(violated)
+        }
+    }
+
+    fn eval_linear_combination(terms: &[Term<F>], witness: &[F]) -> Option<F> {
+        terms.iter().try_fold(F::zero(), |acc, term| {
+            witness.get(term.wire_id as usize).map(|value| acc + *value * term.coefficient)
+        })
+    }
+
+    /// Get the wire-id-to-label-id map, if the file contained a labels section
+    pub fn wire_mapping(&self) -> &[u64] {
+        &self.wire_mapping
+    }
+
+    /// Look up the label id a given wire was compiled from
+    pub fn label_of_wire(&self, wire_id: u32) -> Option<u64> {
+        self.wire_mapping.get(wire_id as usize).copied()
+    }
+
+    /// Get the custom gate definitions, if the file contained a custom-gates-list section
+    pub fn custom_gates(&self) -> &[CustomGate<F>] {
+        &self.custom_gates
+    }
+
+    /// Get the custom gate applications, if the file contained a custom-gates-application section
+    pub fn custom_gate_uses(&self) -> &[CustomGateApplication] {
+        &self.custom_gate_uses
+    }
+
     /// Print detailed information about the R1CS circuit
     pub fn print_info(&self) {
         println!("R1CS Circuit Information:");
@@ -362,13 +871,16 @@ impl R1CS {
         println!("  Private inputs: {}", self.num_private_inputs());
         println!("  Constraints: {}", self.num_constraints());
         println!("  Constraints loaded: {}", self.constraints.len());
-        
+        println!("  Wire labels loaded: {}", self.wire_mapping.len());
+        println!("  Custom gates: {}", self.custom_gates.len());
+        println!("  Custom gate uses: {}", self.custom_gate_uses.len());
+
         // Print the first few bytes of the prime field modulus
         let prime_bytes = self.prime_field_modulus();
         let display_bytes = if prime_bytes.len() > 8 { 8 } else { prime_bytes.len() };
-        println!("  Prime field modulus (first {} bytes): {:?}", 
+        println!("  Prime field modulus (first {} bytes): {:?}",
                  display_bytes, &prime_bytes[..display_bytes]);
-        
+
         // Print a few sample constraints if available
         if !self.constraints.is_empty() {
             println!("\nSample constraints:");
@@ -380,4 +892,206 @@ impl R1CS {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Fr;
+
+    #[test]
+    fn write_then_read_round_trips_header_and_constraints() {
+        let prime_bytes = Fr::MODULUS.to_bytes_le();
+        let header = R1CSHeader {
+            field_size: prime_bytes.len() as u32,
+            prime_bytes,
+            n_wires: 4,
+            n_pub_out: 1,
+            n_pub_in: 1,
+            n_prvt_in: 1,
+            n_labels: 0,
+            n_constraints: 2,
+        };
+        let constraints = vec![
+            R1CSConstraint {
+                a_terms: vec![Term { wire_id: 1, coefficient: Fr::from(3u64) }],
+                b_terms: vec![Term { wire_id: 2, coefficient: Fr::from(5u64) }],
+                c_terms: vec![Term { wire_id: 3, coefficient: Fr::from(15u64) }],
+            },
+            R1CSConstraint {
+                a_terms: vec![
+                    Term { wire_id: 0, coefficient: Fr::from(1u64) },
+                    Term { wire_id: 1, coefficient: Fr::from(2u64) },
+                ],
+                b_terms: vec![],
+                c_terms: vec![Term { wire_id: 3, coefficient: Fr::from(7u64) }],
+            },
+        ];
+        let r1cs = R1CS {
+            header: header.clone(),
+            constraints: constraints.clone(),
+            wire_mapping: Vec::new(),
+            custom_gates: Vec::new(),
+            custom_gate_uses: Vec::new(),
+        };
+
+        let path = std::env::temp_dir().join(format!("r1cs_roundtrip_{}.r1cs", std::process::id()));
+        r1cs.write(&path).expect("writing the R1CS file should succeed");
+        let reread = R1CS::<Fr>::read(&path).expect("reading the R1CS file back should succeed");
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(reread.num_wires(), header.n_wires);
+        assert_eq!(reread.num_public_outputs(), header.n_pub_out);
+        assert_eq!(reread.num_public_inputs(), header.n_pub_in);
+        assert_eq!(reread.num_private_inputs(), header.n_prvt_in);
+        assert_eq!(reread.num_constraints(), header.n_constraints);
+        assert_eq!(reread.prime_field_modulus(), header.prime_bytes.as_slice());
+
+        assert_eq!(reread.constraints().len(), constraints.len());
+        for (expected, actual) in constraints.iter().zip(reread.constraints()) {
+            assert_eq!(expected.a_terms.len(), actual.a_terms.len());
+            assert_eq!(expected.b_terms.len(), actual.b_terms.len());
+            assert_eq!(expected.c_terms.len(), actual.c_terms.len());
+            for (e, a) in expected.a_terms.iter().chain(&expected.b_terms).chain(&expected.c_terms)
+                .zip(actual.a_terms.iter().chain(&actual.b_terms).chain(&actual.c_terms))
+            {
+                assert_eq!(e.wire_id, a.wire_id);
+                assert_eq!(e.coefficient, a.coefficient);
+            }
+        }
+    }
+
+    #[test]
+    fn write_then_read_round_trips_wire_mapping() {
+        let prime_bytes = Fr::MODULUS.to_bytes_le();
+        let header = R1CSHeader {
+            field_size: prime_bytes.len() as u32,
+            prime_bytes,
+            n_wires: 4,
+            n_pub_out: 1,
+            n_pub_in: 1,
+            n_prvt_in: 1,
+            n_labels: 7,
+            n_constraints: 0,
+        };
+        // n_labels (7) deliberately differs from n_wires (4): the wire map holds one
+        // label per wire, not `n_labels` entries.
+        let wire_mapping = vec![10u64, 11, 12, 13];
+        let r1cs = R1CS::<Fr> {
+            header,
+            constraints: Vec::new(),
+            wire_mapping: wire_mapping.clone(),
+            custom_gates: Vec::new(),
+            custom_gate_uses: Vec::new(),
+        };
+
+        let path = std::env::temp_dir().join(format!("r1cs_wire_mapping_{}.r1cs", std::process::id()));
+        r1cs.write(&path).expect("writing the R1CS file should succeed");
+        let reread = R1CS::<Fr>::read(&path).expect("reading the R1CS file back should succeed");
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(reread.wire_mapping(), wire_mapping.as_slice());
+        for (wire_id, &label) in wire_mapping.iter().enumerate() {
+            assert_eq!(reread.label_of_wire(wire_id as u32), Some(label));
+        }
+    }
+
+    fn single_constraint_r1cs(
+        a_terms: Vec<Term<Fr>>,
+        b_terms: Vec<Term<Fr>>,
+        c_terms: Vec<Term<Fr>>,
+    ) -> R1CS<Fr> {
+        let prime_bytes = Fr::MODULUS.to_bytes_le();
+        let header = R1CSHeader {
+            field_size: prime_bytes.len() as u32,
+            prime_bytes,
+            n_wires: 3,
+            n_pub_out: 0,
+            n_pub_in: 0,
+            n_prvt_in: 3,
+            n_labels: 0,
+            n_constraints: 1,
+        };
+        R1CS::<Fr> {
+            header,
+            constraints: vec![R1CSConstraint { a_terms, b_terms, c_terms }],
+            wire_mapping: Vec::new(),
+            custom_gates: Vec::new(),
+            custom_gate_uses: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn check_witness_accepts_a_satisfying_assignment() {
+        let r1cs = single_constraint_r1cs(
+            vec![Term { wire_id: 0, coefficient: Fr::from(1u64) }],
+            vec![Term { wire_id: 1, coefficient: Fr::from(1u64) }],
+            vec![Term { wire_id: 2, coefficient: Fr::from(1u64) }],
+        );
+        let witness = vec![Fr::from(3u64), Fr::from(5u64), Fr::from(15u64)];
+
+        assert_eq!(r1cs.check_witness(&witness), Ok(()));
+    }
+
+    #[test]
+    fn check_witness_reports_violated_constraints() {
+        let r1cs = single_constraint_r1cs(
+            vec![Term { wire_id: 0, coefficient: Fr::from(1u64) }],
+            vec![Term { wire_id: 1, coefficient: Fr::from(1u64) }],
+            vec![Term { wire_id: 2, coefficient: Fr::from(1u64) }],
+        );
+        // 3 * 5 != 16
+        let witness = vec![Fr::from(3u64), Fr::from(5u64), Fr::from(16u64)];
+
+        assert_eq!(r1cs.check_witness(&witness), Err(vec![0]));
+    }
+
+    #[test]
+    fn check_witness_treats_out_of_range_wire_as_violated_instead_of_panicking() {
+        let r1cs = single_constraint_r1cs(
+            vec![Term { wire_id: 5, coefficient: Fr::from(1u64) }],
+            vec![Term { wire_id: 1, coefficient: Fr::from(1u64) }],
+            vec![Term { wire_id: 2, coefficient: Fr::from(1u64) }],
+        );
+        let witness = vec![Fr::from(3u64), Fr::from(5u64), Fr::from(15u64)];
+
+        assert_eq!(r1cs.check_witness(&witness), Err(vec![0]));
+    }
+
+    #[test]
+    fn read_wtns_parses_circoms_binary_witness_format() {
+        let prime_bytes = Fr::MODULUS.to_bytes_le();
+        let field_size = prime_bytes.len() as u32;
+        let values = vec![Fr::from(1u64), Fr::from(42u64), Fr::from(7u64)];
+
+        let mut header_section = Vec::new();
+        header_section.write_u32::<LittleEndian>(field_size).unwrap();
+        header_section.extend_from_slice(&prime_bytes);
+        header_section.write_u32::<LittleEndian>(values.len() as u32).unwrap();
+
+        let mut values_section = Vec::new();
+        for value in &values {
+            let mut bytes = value.into_bigint().to_bytes_le();
+            bytes.resize(field_size as usize, 0);
+            values_section.extend_from_slice(&bytes);
+        }
+
+        let mut buf = Vec::new();
+        buf.extend_from_slice(b"wtns");
+        buf.write_u32::<LittleEndian>(2).unwrap();
+        buf.write_u32::<LittleEndian>(2).unwrap();
+        buf.write_u32::<LittleEndian>(1).unwrap();
+        buf.write_u64::<LittleEndian>(header_section.len() as u64).unwrap();
+        buf.extend_from_slice(&header_section);
+        buf.write_u32::<LittleEndian>(2).unwrap();
+        buf.write_u64::<LittleEndian>(values_section.len() as u64).unwrap();
+        buf.extend_from_slice(&values_section);
+
+        let path = std::env::temp_dir().join(format!("wtns_roundtrip_{}.wtns", std::process::id()));
+        std::fs::write(&path, &buf).expect("writing the scratch .wtns file should succeed");
+        let witness = Witness::<Fr>::read_wtns(&path).expect("reading the witness file should succeed");
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(witness.values, values);
+    }
+}